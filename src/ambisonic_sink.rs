@@ -0,0 +1,182 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::source::{BFormat, SpeakerConfig};
+use crate::stream::{OutputStreamHandle, PlayError};
+use crate::{Sample, Sink, Source};
+
+/// A sink that spatializes its sources in full 3D by encoding them to
+/// first-order Ambisonic (B-format) and decoding to an arbitrary set of
+/// output speakers, instead of the simple two-ear panning used by
+/// [`SpatialSink`](crate::SpatialSink).
+pub struct AmbisonicSink {
+    sink: Sink,
+    state: Arc<Mutex<AmbisonicState>>,
+}
+
+struct AmbisonicState {
+    emitter_position: [f32; 3],
+    speakers: Vec<SpeakerConfig>,
+}
+
+impl AmbisonicSink {
+    /// Builds a new `AmbisonicSink` decoding to the given speaker layout.
+    pub fn try_new(
+        stream: &OutputStreamHandle,
+        emitter_position: [f32; 3],
+        speakers: Vec<SpeakerConfig>,
+    ) -> Result<AmbisonicSink, PlayError> {
+        Ok(AmbisonicSink {
+            sink: Sink::try_new(stream)?,
+            state: Arc::new(Mutex::new(AmbisonicState {
+                emitter_position,
+                speakers,
+            })),
+        })
+    }
+
+    /// Sets the position of the sound emitter in 3 dimensional space.
+    pub fn set_emitter_position(&self, pos: [f32; 3]) {
+        self.state.lock().unwrap().emitter_position = pos;
+    }
+
+    /// Reconfigures the direction and polar pattern of a single output
+    /// speaker.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds for the speaker layout passed to
+    /// [`AmbisonicSink::try_new`].
+    pub fn set_speaker(&self, index: usize, config: SpeakerConfig) {
+        self.state.lock().unwrap().speakers[index] = config;
+    }
+
+    /// Appends a sound to the queue of sounds to play.
+    #[inline]
+    pub fn append<S>(&self, source: S)
+    where
+        S: Source + Send + 'static,
+        S::Item: Sample + Send,
+    {
+        let state = self.state.clone();
+        let state_lock = self.state.lock().unwrap();
+        let source = BFormat::new(
+            source,
+            state_lock.emitter_position,
+            state_lock.speakers.clone(),
+        )
+        .periodic_access(Duration::from_millis(10), move |i| {
+            let state = state.lock().unwrap();
+            i.set_emitter_position(state.emitter_position);
+            i.set_speakers(state.speakers.clone());
+        });
+        self.sink.append(source);
+    }
+
+    /// Gets the volume of the sound.
+    ///
+    /// The value `1.0` is the "normal" volume (unfiltered input). Any value other than 1.0 will
+    /// multiply each sample by this value.
+    #[inline]
+    pub fn volume(&self) -> f32 {
+        self.sink.volume()
+    }
+
+    /// Changes the volume of the sound.
+    ///
+    /// The value `1.0` is the "normal" volume (unfiltered input). Any value other than 1.0 will
+    /// multiply each sample by this value.
+    #[inline]
+    pub fn set_volume(&self, value: f32) {
+        self.sink.set_volume(value);
+    }
+
+    /// Gets the speed of the sound.
+    ///
+    /// The value `1.0` is the "normal" speed (unfiltered input). As with
+    /// [`Sink::speed`], samples are passed to the output unchanged, so the play speed and pitch
+    /// of the sound do not change.
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        self.sink.speed()
+    }
+
+    /// Changes the speed of the sound.
+    ///
+    /// The value `1.0` is the "normal" speed (unfiltered input). As with
+    /// [`Sink::set_speed`], samples are passed to the output unchanged, so the play speed and
+    /// pitch of the sound do not change.
+    #[inline]
+    pub fn set_speed(&self, value: f32) {
+        self.sink.set_speed(value)
+    }
+
+    /// Resumes playback of a paused sound.
+    ///
+    /// No effect if not paused.
+    #[inline]
+    pub fn play(&self) {
+        self.sink.play();
+    }
+
+    /// Pauses playback of this sink.
+    ///
+    /// No effect if already paused.
+    ///
+    /// A paused sound can be resumed with `play()`.
+    pub fn pause(&self) {
+        self.sink.pause();
+    }
+
+    /// Gets if a sound is paused
+    ///
+    /// Sounds can be paused and resumed using pause() and play(). This gets if a sound is paused.
+    pub fn is_paused(&self) -> bool {
+        self.sink.is_paused()
+    }
+
+    /// Removes all currently loaded `Source`s from the `AmbisonicSink` and pauses it.
+    ///
+    /// See `pause()` for information about pausing a `Sink`.
+    #[inline]
+    pub fn clear(&self) {
+        self.sink.clear();
+    }
+
+    /// Stops the sink by emptying the queue.
+    #[inline]
+    pub fn stop(&self) {
+        self.sink.stop()
+    }
+
+    /// Destroys the sink without stopping the sounds that are still playing.
+    #[inline]
+    pub fn detach(self) {
+        self.sink.detach();
+    }
+
+    /// Sleeps the current thread until the sound ends.
+    #[inline]
+    pub fn sleep_until_end(&self) {
+        self.sink.sleep_until_end();
+    }
+
+    /// Returns true if this sink has no more sounds to play.
+    #[inline]
+    pub fn empty(&self) -> bool {
+        self.sink.empty()
+    }
+
+    /// Returns the number of sounds currently in the queue.
+    #[inline]
+    #[allow(clippy::len_without_is_empty)] // `empty()` is this type's `is_empty()`.
+    pub fn len(&self) -> usize {
+        self.sink.len()
+    }
+
+    /// Skips the currently playing sound, moving on to the next one in the
+    /// queue (or silence, if none is queued).
+    #[inline]
+    pub fn skip_one(&self) {
+        self.sink.skip_one();
+    }
+}
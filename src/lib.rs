@@ -0,0 +1,16 @@
+//! Audio playback library.
+
+pub mod ambisonic_sink;
+mod queue;
+mod sample;
+mod sink;
+pub mod source;
+pub mod spatial_sink;
+pub mod stream;
+
+pub use ambisonic_sink::AmbisonicSink;
+pub use sample::Sample;
+pub use sink::Sink;
+pub use source::Source;
+pub use spatial_sink::SpatialSink;
+pub use stream::{OutputStream, OutputStreamHandle, PlayError};
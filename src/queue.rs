@@ -0,0 +1,360 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::source::SeekError;
+use crate::{Sample, Source};
+
+type Boxed<S> = Box<dyn Source<Item = S> + Send>;
+/// A queued source together with the sender (if any) used to signal that it
+/// has been fully played, per [`SourcesQueueInput::append_with_signal`].
+type QueuedSource<S> = (Boxed<S>, Option<Sender<()>>);
+
+/// Builds a new queue. `input` is used to append new sounds, `output` is a
+/// `Source` that plays them back one after the other. `on_track_start` is
+/// called (on the playback thread) every time the output switches to a new
+/// source, e.g. so a wrapping `Sink` can reset its elapsed-position counter.
+pub(crate) fn queue<S>(
+    on_track_start: impl FnMut() + Send + 'static,
+) -> (Arc<SourcesQueueInput<S>>, SourcesQueueOutput<S>)
+where
+    S: Sample + Send + 'static,
+{
+    let input = Arc::new(SourcesQueueInput {
+        next_sounds: Mutex::new(VecDeque::new()),
+        gapless: AtomicBool::new(false),
+        skip_requested: AtomicBool::new(false),
+        clear_requested: AtomicBool::new(false),
+    });
+    let output = SourcesQueueOutput {
+        current: Box::new(Empty::new()),
+        current_signal: None,
+        primed_next: None,
+        input: input.clone(),
+        format_unchanged_on_last_splice: false,
+        on_track_start: Box::new(on_track_start),
+    };
+    (input, output)
+}
+
+/// The producing half of a queue: lets a [`Sink`](crate::Sink) append new
+/// sources.
+pub(crate) struct SourcesQueueInput<S> {
+    next_sounds: Mutex<VecDeque<QueuedSource<S>>>,
+    gapless: AtomicBool,
+    /// Set by [`Self::request_skip`] and consumed by the matching
+    /// [`SourcesQueueOutput`] the next time it produces a sample, so
+    /// [`Sink::skip_one`](crate::Sink::skip_one) can cut the current track
+    /// short from the producing side without holding the output.
+    skip_requested: AtomicBool,
+    /// Set by [`Self::clear`] and consumed by the matching
+    /// [`SourcesQueueOutput`] the next time it produces a sample, so a track
+    /// already primed ahead of time by gapless mode is dropped along with
+    /// the rest of the queue instead of playing anyway.
+    clear_requested: AtomicBool,
+}
+
+impl<S> SourcesQueueInput<S>
+where
+    S: Sample + Send + 'static,
+{
+    /// Appends a source to the queue, returning a receiver that fires once
+    /// it has been fully played.
+    pub(crate) fn append_with_signal<T>(&self, source: T) -> mpsc::Receiver<()>
+    where
+        T: Source<Item = S> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        self.next_sounds
+            .lock()
+            .unwrap()
+            .push_back((Box::new(source), Some(tx)));
+        rx
+    }
+
+    pub(crate) fn clear(&self) {
+        self.next_sounds.lock().unwrap().clear();
+        self.clear_requested.store(true, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_gapless(&self, gapless: bool) {
+        self.gapless.store(gapless, Ordering::Relaxed);
+    }
+
+    /// Requests that the matching [`SourcesQueueOutput`] stop the track it is
+    /// currently playing as soon as possible and move on to the next one (or
+    /// silence, if the queue is empty).
+    pub(crate) fn request_skip(&self) {
+        self.skip_requested.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The consuming half of a queue: a [`Source`] that plays whatever was
+/// pushed through the matching [`SourcesQueueInput`], one after the other.
+pub(crate) struct SourcesQueueOutput<S> {
+    current: Boxed<S>,
+    /// The signal (if any) to fire once `current` has been fully played,
+    /// i.e. on the *next* call to [`Self::advance`], not when `current` was
+    /// installed.
+    current_signal: Option<Sender<()>>,
+    /// When gapless mode is on, the next source is popped off the queue and
+    /// kept ready here ahead of time, so the switch at end-of-track is a
+    /// plain pointer swap instead of a queue pop plus allocation happening
+    /// right at the deadline.
+    primed_next: Option<QueuedSource<S>>,
+    input: Arc<SourcesQueueInput<S>>,
+    /// Whether the most recent splice landed on a source with the same
+    /// channel count and sample rate as the one before it. A consumer that
+    /// caches per-format state (as `Sink` does for its position tracking)
+    /// can check this to skip recomputing that state across the splice.
+    format_unchanged_on_last_splice: bool,
+    on_track_start: Box<dyn FnMut() + Send>,
+}
+
+impl<S> SourcesQueueOutput<S>
+where
+    S: Sample + Send + 'static,
+{
+    fn prime_next_if_gapless(&mut self) {
+        if self.primed_next.is_none() && self.input.gapless.load(Ordering::Relaxed) {
+            self.primed_next = self.input.next_sounds.lock().unwrap().pop_front();
+        }
+    }
+
+    /// Drops a primed-ahead track if [`SourcesQueueInput::clear`] was called
+    /// since it was primed. Without this, a track gapless mode had already
+    /// popped out of `next_sounds` would survive a `clear()`/`stop()` and
+    /// play anyway, since those only empty `next_sounds` itself.
+    fn drop_primed_if_cleared(&mut self) {
+        if self.input.clear_requested.swap(false, Ordering::Relaxed) {
+            self.primed_next = None;
+        }
+    }
+
+    /// Switches to the next queued source, or to silence if none is
+    /// available. Returns whether a new source was found.
+    ///
+    /// This is always the point at which `current` has been fully played
+    /// (its iterator just returned `None`, or it was explicitly skipped), so
+    /// this is where `current`'s completion signal is fired, not when a
+    /// source is first installed as `current`.
+    fn advance(&mut self) -> bool {
+        let previous_format = (self.current.channels(), self.current.sample_rate());
+        let finished_signal = self.current_signal.take();
+
+        let next = self
+            .primed_next
+            .take()
+            .or_else(|| self.input.next_sounds.lock().unwrap().pop_front());
+
+        let found = match next {
+            Some((source, signal)) => {
+                self.format_unchanged_on_last_splice =
+                    previous_format == (source.channels(), source.sample_rate());
+                self.current = source;
+                self.current_signal = signal;
+                (self.on_track_start)();
+                true
+            }
+            None => {
+                self.format_unchanged_on_last_splice = false;
+                self.current = Box::new(Empty::new());
+                false
+            }
+        };
+
+        if let Some(finished_signal) = finished_signal {
+            let _ = finished_signal.send(());
+        }
+
+        found
+    }
+
+    /// See [`Self::format_unchanged_on_last_splice`]'s doc comment.
+    pub(crate) fn format_unchanged_on_last_splice(&self) -> bool {
+        self.format_unchanged_on_last_splice
+    }
+}
+
+impl<S> Iterator for SourcesQueueOutput<S>
+where
+    S: Sample + Send + 'static,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        self.drop_primed_if_cleared();
+        self.prime_next_if_gapless();
+
+        if self.input.skip_requested.swap(false, Ordering::Relaxed) {
+            self.advance();
+        }
+
+        loop {
+            if let Some(sample) = self.current.next() {
+                return Some(sample);
+            }
+            if !self.advance() {
+                return None;
+            }
+        }
+    }
+}
+
+impl<S> Source for SourcesQueueOutput<S>
+where
+    S: Sample + Send + 'static,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.current.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.current.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.current.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.current.try_seek(pos)
+    }
+}
+
+/// A source that yields no samples at all, used as the current source while
+/// the queue is empty.
+struct Empty<S> {
+    _marker: std::marker::PhantomData<S>,
+}
+
+impl<S> Empty<S> {
+    fn new() -> Self {
+        Empty {
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<S> Iterator for Empty<S> {
+    type Item = S;
+
+    fn next(&mut self) -> Option<S> {
+        None
+    }
+}
+
+impl<S> Source for Empty<S>
+where
+    S: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        44_100
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestSource {
+        samples: std::vec::IntoIter<f32>,
+    }
+
+    impl TestSource {
+        fn new(samples: Vec<f32>) -> Self {
+            TestSource {
+                samples: samples.into_iter(),
+            }
+        }
+    }
+
+    impl Iterator for TestSource {
+        type Item = f32;
+
+        fn next(&mut self) -> Option<f32> {
+            self.samples.next()
+        }
+    }
+
+    impl Source for TestSource {
+        fn current_frame_len(&self) -> Option<usize> {
+            None
+        }
+
+        fn channels(&self) -> u16 {
+            1
+        }
+
+        fn sample_rate(&self) -> u32 {
+            1
+        }
+
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
+
+    #[test]
+    fn completion_signal_fires_once_exhausted_not_when_started() {
+        let (input, mut output) = queue::<f32>(|| {});
+        let rx = input.append_with_signal(TestSource::new(vec![1.0, 2.0, 3.0]));
+
+        assert_eq!(output.next(), Some(1.0));
+        assert!(
+            rx.try_recv().is_err(),
+            "signal must not fire while the source is still playing"
+        );
+
+        assert_eq!(output.next(), Some(2.0));
+        assert_eq!(output.next(), Some(3.0));
+        assert!(
+            rx.try_recv().is_err(),
+            "signal must not fire before the source is exhausted"
+        );
+
+        assert_eq!(output.next(), None);
+        assert!(
+            rx.try_recv().is_ok(),
+            "signal must fire once the source has been fully played"
+        );
+    }
+
+    #[test]
+    fn clear_cancels_a_track_already_primed_by_gapless_mode() {
+        let (input, mut output) = queue::<f32>(|| {});
+        input.set_gapless(true);
+        input.append_with_signal(TestSource::new(vec![1.0, 2.0]));
+        input.append_with_signal(TestSource::new(vec![3.0]));
+
+        assert_eq!(output.next(), Some(1.0));
+        // Consuming the first track's last sample also primes the second
+        // track ahead of time (popping it out of `next_sounds`), since
+        // gapless mode is on.
+        assert_eq!(output.next(), Some(2.0));
+
+        // Cancelling the queue must also cancel the already-primed track,
+        // not just the ones still sitting in `next_sounds`.
+        input.clear();
+
+        assert_eq!(output.next(), None);
+    }
+}
@@ -0,0 +1,23 @@
+use cpal::FromSample;
+
+/// Represents a value of a single audio sample.
+///
+/// This is a thin wrapper over [`cpal::Sample`] so the rest of the crate can
+/// talk about "a sample type" without naming `cpal` everywhere. It is
+/// implemented for every type `cpal::Sample` already covers (`i16`, `u16`,
+/// `f32`, ...).
+pub trait Sample: cpal::Sample + Send {
+    /// Converts this sample to `f32`, the format all internal mixing and
+    /// resampling is done in.
+    fn to_f32(self) -> f32;
+}
+
+impl<S> Sample for S
+where
+    S: cpal::Sample + Send,
+    f32: FromSample<S>,
+{
+    fn to_f32(self) -> f32 {
+        FromSample::from_sample_(self)
+    }
+}
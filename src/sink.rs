@@ -0,0 +1,508 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use crate::queue::{self, SourcesQueueInput, SourcesQueueOutput};
+use crate::source::{Done, SeekError};
+use crate::stream::{OutputStreamHandle, PlayError};
+use crate::{Sample, Source};
+
+/// A callback registered through [`Sink::on_track_end_with_progress`],
+/// receiving the index of the finished source and the number of sources
+/// still remaining in the queue.
+type TrackEndWithProgressCallback = Box<dyn Fn(usize, usize) + Send>;
+
+/// A request to seek the currently playing source, serviced by the playback
+/// thread the next time it pulls a sample.
+struct PendingSeek {
+    pos: Duration,
+    result_tx: SyncSender<Result<(), SeekError>>,
+}
+
+/// State shared between the public `Sink` handle and the playback thread
+/// that actually drains the queue.
+struct Controls {
+    pause: AtomicBool,
+    stopped: AtomicBool,
+    volume: Mutex<f32>,
+    speed: Mutex<f32>,
+    /// Position within the currently playing source divided by the current
+    /// `speed()` (see [`Sink::get_pos`]), updated on the playback thread
+    /// proportionally to samples consumed, reset to zero whenever a new
+    /// source starts and set from the target whenever a seek lands.
+    elapsed: RwLock<Duration>,
+    pending_seek: Mutex<Option<PendingSeek>>,
+    next_track_index: AtomicUsize,
+    on_track_end: Mutex<Vec<Box<dyn Fn() + Send>>>,
+    on_track_end_with_progress: Mutex<Vec<TrackEndWithProgressCallback>>,
+}
+
+/// Handle to a queue of sounds being played.
+///
+/// Dropping the `Sink` stops all sounds it plays, unless [`Sink::detach`] is
+/// called first.
+pub struct Sink {
+    queue_tx: Arc<SourcesQueueInput<f32>>,
+    sleep_until_end: Mutex<Option<Receiver<()>>>,
+    controls: Arc<Controls>,
+    sound_count: Arc<AtomicUsize>,
+    detached: bool,
+}
+
+impl Sink {
+    /// Builds a new `Sink`, placing it on the given stream.
+    pub fn try_new(stream: &OutputStreamHandle) -> Result<Sink, PlayError> {
+        let controls = Arc::new(Controls {
+            pause: AtomicBool::new(false),
+            stopped: AtomicBool::new(false),
+            volume: Mutex::new(1.0),
+            speed: Mutex::new(1.0),
+            elapsed: RwLock::new(Duration::ZERO),
+            pending_seek: Mutex::new(None),
+            next_track_index: AtomicUsize::new(0),
+            on_track_end: Mutex::new(Vec::new()),
+            on_track_end_with_progress: Mutex::new(Vec::new()),
+        });
+
+        let (queue_tx, queue_rx) = {
+            let controls = controls.clone();
+            queue::queue(move || {
+                *controls.elapsed.write().unwrap() = Duration::ZERO;
+            })
+        };
+
+        stream.play_raw(TrackedQueueOutput {
+            inner: queue_rx,
+            controls: controls.clone(),
+            cached_samples_per_sec: 44_100.0,
+        })?;
+
+        Ok(Sink {
+            queue_tx,
+            sleep_until_end: Mutex::new(None),
+            controls,
+            sound_count: Arc::new(AtomicUsize::new(0)),
+            detached: false,
+        })
+    }
+
+    /// Appends a sound to the queue of sounds to play.
+    pub fn append<S>(&self, source: S)
+    where
+        S: Source + Send + 'static,
+        S::Item: Sample,
+    {
+        let index = self.controls.next_track_index.fetch_add(1, Ordering::Relaxed);
+        self.sound_count.fetch_add(1, Ordering::Relaxed);
+
+        let sound_count = self.sound_count.clone();
+        let controls = self.controls.clone();
+        let source = ToF32::new(source);
+        let source = Done::new(source, move || {
+            let remaining = sound_count.fetch_sub(1, Ordering::Relaxed) - 1;
+            for f in controls.on_track_end.lock().unwrap().iter() {
+                f();
+            }
+            for f in controls.on_track_end_with_progress.lock().unwrap().iter() {
+                f(index, remaining);
+            }
+        });
+
+        let receiver = self.queue_tx.append_with_signal(source);
+        *self.sleep_until_end.lock().unwrap() = Some(receiver);
+    }
+
+    /// Attempts to seek to a given position within the currently playing
+    /// source. Has no effect if the sink is empty.
+    ///
+    /// # Errors
+    /// See [`Source::try_seek`].
+    pub fn try_seek(&self, pos: Duration) -> Result<(), SeekError> {
+        let (result_tx, result_rx) = mpsc::sync_channel(1);
+        *self.controls.pending_seek.lock().unwrap() = Some(PendingSeek { pos, result_tx });
+        result_rx
+            .recv()
+            .unwrap_or(Err(SeekError::NotSupported {
+                underlying_source: "empty sink",
+            }))
+    }
+
+    /// Returns the position of the sound that's being played.
+    ///
+    /// This takes into account any speedup or slowdown applied.
+    ///
+    /// Example: if you apply a speedup of *2* to an mp3 decoder source and
+    /// `get_pos()` returns *5s* after playback started, the position in the
+    /// mp3 recording is *10s* from its start.
+    #[inline]
+    pub fn get_pos(&self) -> Duration {
+        *self.controls.elapsed.read().unwrap()
+    }
+
+    /// Registers a callback invoked every time a queued source has been
+    /// fully consumed.
+    pub fn on_track_end(&self, f: impl Fn() + Send + 'static) {
+        self.controls.on_track_end.lock().unwrap().push(Box::new(f));
+    }
+
+    /// Registers a callback invoked every time a queued source has been
+    /// fully consumed, receiving the index of the finished source and the
+    /// number of sources still remaining in the queue.
+    pub fn on_track_end_with_progress(&self, f: impl Fn(usize, usize) + Send + 'static) {
+        self.controls
+            .on_track_end_with_progress
+            .lock()
+            .unwrap()
+            .push(Box::new(f));
+    }
+
+    /// Gets the volume of the sound.
+    ///
+    /// The value `1.0` is the "normal" volume (unfiltered input). Any value other than 1.0 will
+    /// multiply each sample by this value.
+    #[inline]
+    pub fn volume(&self) -> f32 {
+        *self.controls.volume.lock().unwrap()
+    }
+
+    /// Changes the volume of the sound.
+    ///
+    /// The value `1.0` is the "normal" volume (unfiltered input). Any value other than 1.0 will
+    /// multiply each sample by this value.
+    #[inline]
+    pub fn set_volume(&self, value: f32) {
+        *self.controls.volume.lock().unwrap() = value;
+    }
+
+    /// Gets the speed of the sound.
+    ///
+    /// The value `1.0` is the "normal" speed (unfiltered input). The speed only scales the
+    /// position reported by [`Sink::get_pos`]; samples are passed to the output unchanged, so
+    /// the play speed and pitch of the sound do not change.
+    #[inline]
+    pub fn speed(&self) -> f32 {
+        *self.controls.speed.lock().unwrap()
+    }
+
+    /// Changes the speed of the sound.
+    ///
+    /// The value `1.0` is the "normal" speed (unfiltered input). The speed only scales the
+    /// position reported by [`Sink::get_pos`]; samples are passed to the output unchanged, so
+    /// the play speed and pitch of the sound do not change.
+    #[inline]
+    pub fn set_speed(&self, value: f32) {
+        *self.controls.speed.lock().unwrap() = value;
+    }
+
+    /// Resumes playback of a paused sound.
+    ///
+    /// No effect if not paused.
+    #[inline]
+    pub fn play(&self) {
+        self.controls.pause.store(false, Ordering::SeqCst);
+    }
+
+    /// Pauses playback of this sink.
+    ///
+    /// No effect if already paused.
+    ///
+    /// A paused sound can be resumed with `play()`.
+    pub fn pause(&self) {
+        self.controls.pause.store(true, Ordering::SeqCst);
+    }
+
+    /// Gets if a sound is paused
+    ///
+    /// Sounds can be paused and resumed using pause() and play(). This gets if a sound is paused.
+    pub fn is_paused(&self) -> bool {
+        self.controls.pause.load(Ordering::SeqCst)
+    }
+
+    /// Removes all currently loaded `Source`s from the `Sink` and pauses it.
+    ///
+    /// See `pause()` for information about pausing a `Sink`.
+    #[inline]
+    pub fn clear(&self) {
+        self.queue_tx.clear();
+        self.queue_tx.request_skip();
+        self.pause();
+    }
+
+    /// Stops the sink by emptying the queue.
+    #[inline]
+    pub fn stop(&self) {
+        self.queue_tx.clear();
+    }
+
+    /// Sets whether the sink splices consecutive sources without a gap.
+    ///
+    /// When enabled, the next queued source is primed ahead of the current
+    /// one finishing, so the switch happens on the sample boundary instead of
+    /// after a queue pop and allocation at the deadline.
+    #[inline]
+    pub fn set_gapless(&self, gapless: bool) {
+        self.queue_tx.set_gapless(gapless);
+    }
+
+    /// Skips the currently playing sound, moving on to the next one in the
+    /// queue (or silence, if none is queued).
+    #[inline]
+    pub fn skip_one(&self) {
+        self.queue_tx.request_skip();
+    }
+
+    /// Destroys the sink without stopping the sounds that are still playing.
+    #[inline]
+    pub fn detach(mut self) {
+        self.detached = true;
+    }
+
+    /// Sleeps the current thread until the sound ends.
+    #[inline]
+    pub fn sleep_until_end(&self) {
+        if let Some(receiver) = self.sleep_until_end.lock().unwrap().take() {
+            let _ = receiver.recv();
+        }
+    }
+
+    /// Returns true if this sink has no more sounds to play.
+    #[inline]
+    pub fn empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of sounds currently in the queue.
+    #[inline]
+    #[allow(clippy::len_without_is_empty)] // `empty()` is this type's `is_empty()`.
+    pub fn len(&self) -> usize {
+        self.sound_count.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for Sink {
+    fn drop(&mut self) {
+        if !self.detached {
+            self.queue_tx.clear();
+            self.controls.stopped.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Converts an arbitrary sample type to `f32`, the type the queue and the
+/// rest of the sink pipeline operate on uniformly.
+struct ToF32<I> {
+    inner: I,
+}
+
+impl<I> ToF32<I> {
+    fn new(inner: I) -> Self {
+        ToF32 { inner }
+    }
+}
+
+impl<I> Iterator for ToF32<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.inner.next().map(Sample::to_f32)
+    }
+}
+
+impl<I> Source for ToF32<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)
+    }
+}
+
+/// Wraps the queue output with volume/pause control, with the position
+/// tracking behind [`Sink::get_pos`] and with the seek handling described on
+/// [`Sink::try_seek`]. This is the `Source` actually
+/// handed to the output stream, so its `next()` runs on the playback
+/// thread.
+struct TrackedQueueOutput {
+    inner: SourcesQueueOutput<f32>,
+    controls: Arc<Controls>,
+    /// `channels() * sample_rate()` of the source last seen by
+    /// `track_elapsed`, cached so a gapless splice between two sources of
+    /// the same format doesn't redo this lookup every sample.
+    cached_samples_per_sec: f64,
+}
+
+impl TrackedQueueOutput {
+    fn service_pending_seek(&mut self) {
+        let pending = self.controls.pending_seek.lock().unwrap().take();
+        if let Some(PendingSeek { pos, result_tx }) = pending {
+            let result = self.inner.try_seek(pos);
+            if result.is_ok() {
+                let speed = (*self.controls.speed.lock().unwrap() as f64).max(f64::MIN_POSITIVE);
+                *self.controls.elapsed.write().unwrap() = pos.div_f64(speed);
+            }
+            let _ = result_tx.send(result);
+        }
+    }
+
+    fn track_elapsed(&mut self) {
+        if !self.inner.format_unchanged_on_last_splice() {
+            let channels = self.inner.channels().max(1) as f64;
+            let sample_rate = self.inner.sample_rate().max(1) as f64;
+            self.cached_samples_per_sec = sample_rate * channels;
+        }
+        // Each sample advances the position *within the source* by a fixed
+        // `1 / cached_samples_per_sec`, regardless of speed. At 2x speed,
+        // that same chunk of source material is meant to go by in half the
+        // wall-clock time, so the elapsed-time estimate this drives
+        // (get_pos()) must divide by speed, not multiply by it — see the
+        // doc example on `Sink::get_pos`.
+        let speed = (*self.controls.speed.lock().unwrap() as f64).max(f64::MIN_POSITIVE);
+        let dt = Duration::from_secs_f64(1.0 / (speed * self.cached_samples_per_sec));
+        *self.controls.elapsed.write().unwrap() += dt;
+    }
+}
+
+impl Iterator for TrackedQueueOutput {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.service_pending_seek();
+
+        if self.controls.stopped.load(Ordering::Relaxed) {
+            return None;
+        }
+        if self.controls.pause.load(Ordering::SeqCst) {
+            return Some(0.0);
+        }
+
+        let sample = self.inner.next()?;
+        self.track_elapsed();
+        let volume = *self.controls.volume.lock().unwrap();
+        Some(sample * volume)
+    }
+}
+
+impl Source for TrackedQueueOutput {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        let result = self.inner.try_seek(pos);
+        if result.is_ok() {
+            let speed = (*self.controls.speed.lock().unwrap() as f64).max(f64::MIN_POSITIVE);
+            *self.controls.elapsed.write().unwrap() = pos.div_f64(speed);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Silence {
+        channels: u16,
+        sample_rate: u32,
+    }
+
+    impl Iterator for Silence {
+        type Item = f32;
+
+        fn next(&mut self) -> Option<f32> {
+            Some(0.0)
+        }
+    }
+
+    impl Source for Silence {
+        fn current_frame_len(&self) -> Option<usize> {
+            None
+        }
+
+        fn channels(&self) -> u16 {
+            self.channels
+        }
+
+        fn sample_rate(&self) -> u32 {
+            self.sample_rate
+        }
+
+        fn total_duration(&self) -> Option<Duration> {
+            None
+        }
+    }
+
+    fn controls_with_speed(speed: f32) -> Arc<Controls> {
+        Arc::new(Controls {
+            pause: AtomicBool::new(false),
+            stopped: AtomicBool::new(false),
+            volume: Mutex::new(1.0),
+            speed: Mutex::new(speed),
+            elapsed: RwLock::new(Duration::ZERO),
+            pending_seek: Mutex::new(None),
+            next_track_index: AtomicUsize::new(0),
+            on_track_end: Mutex::new(Vec::new()),
+            on_track_end_with_progress: Mutex::new(Vec::new()),
+        })
+    }
+
+    #[test]
+    fn doubling_speed_halves_reported_elapsed_time() {
+        let controls = controls_with_speed(2.0);
+        let (queue_tx, queue_rx) = queue::queue::<f32>(|| {});
+        // 4 samples at a native rate of 4 samples/sec is exactly "1 real
+        // second" worth of source material.
+        queue_tx.append_with_signal(Silence {
+            channels: 1,
+            sample_rate: 4,
+        });
+        let mut tracked = TrackedQueueOutput {
+            inner: queue_rx,
+            controls: controls.clone(),
+            cached_samples_per_sec: 44_100.0,
+        };
+
+        for _ in 0..4 {
+            tracked.next();
+        }
+
+        // At 2x speed, that 1 second of source material should be reported
+        // as having taken half as long to play, per `Sink::get_pos`'s doc
+        // example.
+        assert_eq!(*controls.elapsed.read().unwrap(), Duration::from_millis(500));
+    }
+}
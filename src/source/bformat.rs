@@ -0,0 +1,219 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::source::linear_resampler::LinearResampler;
+use crate::source::SeekError;
+use crate::{Sample, Source};
+
+/// The direction and polar pattern of a single output speaker (or virtual
+/// ear) used to decode a B-format signal.
+#[derive(Clone, Copy, Debug)]
+pub struct SpeakerConfig {
+    /// Direction the speaker is placed in, as a point on the unit sphere.
+    pub direction: [f32; 3],
+    /// Polar pattern of the decoding virtual microphone: `1.0` is
+    /// omnidirectional, `0.5` is cardioid and `0.0` is figure-eight.
+    pub pattern: f32,
+}
+
+/// The four channels of a first-order Ambisonic (B-format) signal.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct BFormatChannels {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+/// Encodes a mono sample coming from `dir` (a unit vector, or the zero
+/// vector if the emitter sits on top of the listener) at the given distance
+/// attenuation into B-format.
+pub(crate) fn encode(sample: f32, dir: [f32; 3], attenuation: f32) -> BFormatChannels {
+    let w = std::f32::consts::FRAC_1_SQRT_2;
+    BFormatChannels {
+        w: sample * w * attenuation,
+        x: sample * dir[0] * attenuation,
+        y: sample * dir[1] * attenuation,
+        z: sample * dir[2] * attenuation,
+    }
+}
+
+/// Computes the weights of a virtual microphone pointed in `dir` with polar
+/// pattern `p` (`1.0` omni, `0.5` cardioid, `0.0` figure-eight), used to
+/// decode a B-format signal for a single output speaker.
+pub(crate) fn virtual_microphone(dir: [f32; 3], p: f32) -> BFormatChannels {
+    BFormatChannels {
+        w: p * std::f32::consts::SQRT_2,
+        x: (1.0 - p) * dir[0],
+        y: (1.0 - p) * dir[1],
+        z: (1.0 - p) * dir[2],
+    }
+}
+
+/// Decodes a B-format signal for a single speaker given its virtual
+/// microphone weights.
+pub(crate) fn decode(bformat: BFormatChannels, mic: BFormatChannels) -> f32 {
+    bformat.w * mic.w + bformat.x * mic.x + bformat.y * mic.y + bformat.z * mic.z
+}
+
+fn direction_and_attenuation(emitter_position: [f32; 3]) -> ([f32; 3], f32) {
+    let dist = (emitter_position[0] * emitter_position[0]
+        + emitter_position[1] * emitter_position[1]
+        + emitter_position[2] * emitter_position[2])
+        .sqrt();
+    let attenuation = 1.0 / dist.max(1.0);
+    if dist == 0.0 {
+        ([0.0, 0.0, 0.0], attenuation)
+    } else {
+        (
+            [
+                emitter_position[0] / dist,
+                emitter_position[1] / dist,
+                emitter_position[2] / dist,
+            ],
+            attenuation,
+        )
+    }
+}
+
+/// Encodes a mono emitter into first-order Ambisonic (B-format) and decodes
+/// it straight to an arbitrary set of output speakers, enabling full 3D
+/// placement (including elevation) instead of the two-ear panning used by
+/// [`Spatial`](crate::source::Spatial).
+///
+/// Used internally by [`AmbisonicSink`](crate::AmbisonicSink).
+pub struct BFormat<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    resampler: LinearResampler<I>,
+    sample_rate: u32,
+    total_duration: Option<Duration>,
+    emitter_position: [f32; 3],
+    speakers: Vec<SpeakerConfig>,
+    pending: VecDeque<f32>,
+}
+
+impl<I> BFormat<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    pub fn new(input: I, emitter_position: [f32; 3], speakers: Vec<SpeakerConfig>) -> Self {
+        let sample_rate = input.sample_rate();
+        let total_duration = input.total_duration();
+        BFormat {
+            resampler: LinearResampler::new(input),
+            sample_rate,
+            total_duration,
+            emitter_position,
+            speakers,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Updates the position of the sound emitter in 3 dimensional space.
+    pub fn set_emitter_position(&mut self, pos: [f32; 3]) {
+        self.emitter_position = pos;
+    }
+
+    /// Replaces the direction/pattern configuration of every output speaker.
+    pub fn set_speakers(&mut self, speakers: Vec<SpeakerConfig>) {
+        self.speakers = speakers;
+    }
+}
+
+impl<I> Iterator for BFormat<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(sample) = self.pending.pop_front() {
+            return Some(sample);
+        }
+
+        let sample = self.resampler.next_sample(1.0)?;
+        let (dir, attenuation) = direction_and_attenuation(self.emitter_position);
+        let bformat = encode(sample, dir, attenuation);
+
+        for speaker in &self.speakers {
+            let mic = virtual_microphone(speaker.direction, speaker.pattern);
+            self.pending.push_back(decode(bformat, mic));
+        }
+
+        self.pending.pop_front()
+    }
+}
+
+impl<I> Source for BFormat<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.resampler.inner().current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.speakers.len().max(1) as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.total_duration
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.resampler.try_seek(pos)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn omni_microphone_picks_up_every_direction_equally() {
+        let bformat = encode(1.0, [1.0, 0.0, 0.0], 1.0);
+        let mic = virtual_microphone([0.0, 1.0, 0.0], 1.0);
+        // `p = 1.0` (omni) must zero out the directional terms, leaving only `w`.
+        assert_eq!(mic.x, 0.0);
+        assert_eq!(mic.y, 0.0);
+        assert_eq!(mic.z, 0.0);
+        assert_eq!(decode(bformat, mic), bformat.w * std::f32::consts::SQRT_2);
+    }
+
+    #[test]
+    fn figure_eight_microphone_has_no_omni_component() {
+        let mic = virtual_microphone([1.0, 0.0, 0.0], 0.0);
+        assert_eq!(mic.w, 0.0);
+        assert_eq!(mic.x, 1.0);
+    }
+
+    #[test]
+    fn cardioid_microphone_facing_source_picks_up_full_signal() {
+        let bformat = encode(1.0, [1.0, 0.0, 0.0], 1.0);
+        let mic = virtual_microphone([1.0, 0.0, 0.0], 0.5);
+        let decoded = decode(bformat, mic);
+        // w contributes w*w_mic = (1/sqrt2)*(0.5*sqrt2) = 0.5, x contributes
+        // x*x_mic = 1*(0.5) = 0.5, for a combined unity gain on-axis.
+        assert!((decoded - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn distance_attenuation_is_clamped_within_one_meter() {
+        let (_, close) = direction_and_attenuation([0.1, 0.0, 0.0]);
+        let (_, far) = direction_and_attenuation([10.0, 0.0, 0.0]);
+        assert_eq!(close, 1.0);
+        assert!((far - 0.1).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use crate::source::SeekError;
+use crate::{Sample, Source};
+
+/// Wraps a source and invokes a callback exactly once, the moment the source
+/// is fully consumed (its iterator first returns `None`).
+///
+/// `Sink::append` wraps every appended source in a `Done` so it can fire
+/// `on_track_end`/`on_track_end_with_progress` callbacks. `sleep_until_end`
+/// is resolved separately, by the queue's own completion signal.
+pub struct Done<I> {
+    input: I,
+    on_done: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl<I> Done<I> {
+    pub fn new(input: I, on_done: impl FnOnce() + Send + 'static) -> Self {
+        Done {
+            input,
+            on_done: Some(Box::new(on_done)),
+        }
+    }
+}
+
+impl<I> Iterator for Done<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let item = self.input.next();
+        if item.is_none() {
+            if let Some(on_done) = self.on_done.take() {
+                on_done();
+            }
+        }
+        item
+    }
+}
+
+impl<I> Source for Done<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
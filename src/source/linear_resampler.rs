@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use crate::source::SeekError;
+use crate::{Sample, Source};
+
+/// Reads an inner [`Source`] one sample at a time while letting the read
+/// rate be scaled by an arbitrary, live-adjustable ratio.
+///
+/// A `ratio` of `1.0` passes samples through unchanged. A `ratio` above
+/// `1.0` reads the input faster than real time (raising pitch), a ratio
+/// below `1.0` reads it slower (lowering pitch). This is the machinery
+/// behind [`Spatial`](crate::source::Spatial)'s Doppler shifting.
+pub(crate) struct LinearResampler<I> {
+    input: I,
+    current: f32,
+    next: f32,
+    frac: f32,
+    ended: bool,
+}
+
+impl<I> LinearResampler<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    pub(crate) fn new(mut input: I) -> Self {
+        let current = input.next().map(Sample::to_f32).unwrap_or(0.0);
+        let next = input.next().map(Sample::to_f32).unwrap_or(current);
+        LinearResampler {
+            input,
+            current,
+            next,
+            frac: 0.0,
+            ended: false,
+        }
+    }
+
+    pub(crate) fn inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Produces the next resampled sample, advancing the read position by
+    /// `ratio`. Returns `None` once the input is exhausted.
+    pub(crate) fn next_sample(&mut self, ratio: f32) -> Option<f32> {
+        if self.ended {
+            return None;
+        }
+
+        let ratio = if ratio.is_finite() && ratio > 0.0 {
+            ratio
+        } else {
+            1.0
+        };
+
+        let sample = self.current + (self.next - self.current) * self.frac;
+        self.frac += ratio;
+
+        while self.frac >= 1.0 {
+            self.frac -= 1.0;
+            self.current = self.next;
+            match self.input.next() {
+                Some(s) => self.next = s.to_f32(),
+                None => {
+                    self.ended = true;
+                    break;
+                }
+            }
+        }
+
+        Some(sample)
+    }
+
+    /// Forwards a seek to the wrapped source and resynchronizes the
+    /// interpolation state, so spatialization/resampling continues
+    /// correctly from the new position.
+    pub(crate) fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frac = 0.0;
+        self.ended = false;
+        self.current = self.input.next().map(Sample::to_f32).unwrap_or(0.0);
+        self.next = self.input.next().map(Sample::to_f32).unwrap_or(self.current);
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use crate::Sample;
+
+mod bformat;
+mod done;
+mod linear_resampler;
+mod periodic_access;
+mod seek_error;
+mod spatial;
+
+pub use bformat::{BFormat, SpeakerConfig};
+pub use done::Done;
+pub use periodic_access::PeriodicAccess;
+pub use seek_error::SeekError;
+pub use spatial::Spatial;
+
+/// A source of samples, akin to an `Iterator` that returns `None` once it
+/// has been fully played.
+///
+/// Channels are interleaved: for stereo audio, the iterator alternates
+/// between samples for the left and right channels.
+pub trait Source: Iterator
+where
+    Self::Item: Sample,
+{
+    /// Returns the number of samples before the current frame ends (i.e.
+    /// before the values returned by [`Source::channels`] and
+    /// [`Source::sample_rate`] may change), or `None` if it is unknown.
+    fn current_frame_len(&self) -> Option<usize>;
+
+    /// Returns the number of channels. Channels are always interleaved.
+    fn channels(&self) -> u16;
+
+    /// Returns the rate at which samples are played, in samples per second.
+    fn sample_rate(&self) -> u32;
+
+    /// Returns the total duration of this source, if known.
+    fn total_duration(&self) -> Option<Duration>;
+
+    /// Attempts to seek to a given position within this source.
+    ///
+    /// As not all sources support seeking, the default implementation
+    /// always fails with [`SeekError::NotSupported`]. Decoder-backed
+    /// sources override this to reset the underlying reader's sample
+    /// position.
+    ///
+    /// # Errors
+    /// Returns [`SeekError::NotSupported`] if the source does not support
+    /// seeking, or another [`SeekError`] variant if the implementation ran
+    /// into one while seeking.
+    fn try_seek(&mut self, _pos: Duration) -> Result<(), SeekError> {
+        Err(SeekError::NotSupported {
+            underlying_source: std::any::type_name::<Self>(),
+        })
+    }
+
+    /// Calls `access` every `period`, passing it a mutable reference to
+    /// `self`, so live state (e.g. source positions) can be pushed into the
+    /// source while it plays.
+    fn periodic_access<F>(self, period: Duration, access: F) -> PeriodicAccess<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&mut Self),
+    {
+        PeriodicAccess::new(self, period, access)
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use crate::source::SeekError;
+use crate::{Sample, Source};
+
+/// Calls a function on the wrapped source at a fixed period, measured in the
+/// source's own playback time. Built by [`Source::periodic_access`].
+pub struct PeriodicAccess<S, F> {
+    inner: S,
+    func: F,
+    period: Duration,
+    samples_until_update: u32,
+}
+
+impl<S, F> PeriodicAccess<S, F>
+where
+    S: Source,
+    S::Item: Sample,
+    F: FnMut(&mut S),
+{
+    pub(crate) fn new(inner: S, period: Duration, func: F) -> Self {
+        let samples_until_update = Self::samples_per_period(&inner, period);
+        PeriodicAccess {
+            inner,
+            func,
+            period,
+            samples_until_update,
+        }
+    }
+
+    fn samples_per_period(inner: &S, period: Duration) -> u32 {
+        let channels = inner.channels().max(1) as f32;
+        let sample_rate = inner.sample_rate().max(1) as f32;
+        let samples = period.as_secs_f32() * sample_rate * channels;
+        (samples.round() as u32).max(1)
+    }
+}
+
+impl<S, F> Iterator for PeriodicAccess<S, F>
+where
+    S: Source,
+    S::Item: Sample,
+    F: FnMut(&mut S),
+{
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        if self.samples_until_update == 0 {
+            (self.func)(&mut self.inner);
+            self.samples_until_update = Self::samples_per_period(&self.inner, self.period);
+        }
+        self.samples_until_update = self.samples_until_update.saturating_sub(1);
+        self.inner.next()
+    }
+}
+
+impl<S, F> Source for PeriodicAccess<S, F>
+where
+    S: Source,
+    S::Item: Sample,
+    F: FnMut(&mut S),
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.inner.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+
+    /// Forwards the seek to the wrapped source, so e.g. a [`Spatial`](crate::source::Spatial)
+    /// source wrapped through [`Source::periodic_access`] keeps spatializing correctly
+    /// after the jump.
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.inner.try_seek(pos)
+    }
+}
@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error returned by [`Source::try_seek`](crate::Source::try_seek).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SeekError {
+    /// The underlying source does not implement seeking at all. This is the
+    /// default behavior of [`Source::try_seek`](crate::Source::try_seek).
+    NotSupported {
+        /// Name of the source type that does not support seeking.
+        underlying_source: &'static str,
+    },
+    /// The seek target lies at or beyond a point whose position cannot be
+    /// determined because the source's total duration is unknown.
+    DurationUnknown,
+}
+
+impl fmt::Display for SeekError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeekError::NotSupported { underlying_source } => {
+                write!(f, "source {underlying_source} does not support seeking")
+            }
+            SeekError::DurationUnknown => {
+                write!(f, "cannot seek in a source of unknown duration")
+            }
+        }
+    }
+}
+
+impl Error for SeekError {}
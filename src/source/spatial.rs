@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use crate::source::linear_resampler::LinearResampler;
+use crate::source::SeekError;
+use crate::{Sample, Source};
+
+/// Pans a mono source between a left and a right ear based on their
+/// distance from an emitter, producing an interleaved stereo [`Source`].
+///
+/// Used internally by [`SpatialSink`](crate::SpatialSink).
+pub struct Spatial<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    resampler: LinearResampler<I>,
+    sample_rate: u32,
+    total_duration: Option<Duration>,
+    left_gain: f32,
+    right_gain: f32,
+    pending_right: Option<f32>,
+    /// Playback-rate multiplier applied when reading the input, used to
+    /// drive effects such as a Doppler pitch shift. `1.0` leaves samples
+    /// untouched.
+    speed_ratio: f32,
+}
+
+impl<I> Spatial<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    pub fn new(
+        input: I,
+        emitter_position: [f32; 3],
+        left_ear: [f32; 3],
+        right_ear: [f32; 3],
+    ) -> Self {
+        let sample_rate = input.sample_rate();
+        let total_duration = input.total_duration();
+        let (left_gain, right_gain) = Self::gains(emitter_position, left_ear, right_ear);
+
+        Spatial {
+            resampler: LinearResampler::new(input),
+            sample_rate,
+            total_duration,
+            left_gain,
+            right_gain,
+            pending_right: None,
+            speed_ratio: 1.0,
+        }
+    }
+
+    fn gains(emitter_position: [f32; 3], left_ear: [f32; 3], right_ear: [f32; 3]) -> (f32, f32) {
+        let left_gain = 1.0 / distance(emitter_position, left_ear).max(1.0);
+        let right_gain = 1.0 / distance(emitter_position, right_ear).max(1.0);
+        (left_gain, right_gain)
+    }
+
+    /// Updates the emitter and ear positions used for panning.
+    pub fn set_positions(
+        &mut self,
+        emitter_position: [f32; 3],
+        left_ear: [f32; 3],
+        right_ear: [f32; 3],
+    ) {
+        let (left_gain, right_gain) = Self::gains(emitter_position, left_ear, right_ear);
+        self.left_gain = left_gain;
+        self.right_gain = right_gain;
+    }
+
+    /// Sets the extra playback-rate multiplier described on [`Self::speed_ratio`].
+    ///
+    /// This is independent of [`Sink::set_speed`](crate::Sink::set_speed),
+    /// which does not change how samples are read: this ratio alone sets the
+    /// rate at which the input is read.
+    pub fn set_speed(&mut self, ratio: f32) {
+        self.speed_ratio = ratio;
+    }
+}
+
+impl<I> Iterator for Spatial<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if let Some(right) = self.pending_right.take() {
+            return Some(right);
+        }
+
+        let sample = self.resampler.next_sample(self.speed_ratio)?;
+        self.pending_right = Some(sample * self.right_gain);
+        Some(sample * self.left_gain)
+    }
+}
+
+impl<I> Source for Spatial<I>
+where
+    I: Source,
+    I::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.resampler.inner().current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        2
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.total_duration
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.resampler.try_seek(pos)?;
+        self.pending_right = None;
+        Ok(())
+    }
+}
+
+/// Returns the Euclidean distance between two points in 3D space.
+pub(crate) fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let d = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::distance;
+
+    #[test]
+    fn distance_between_identical_points_is_zero() {
+        assert_eq!(distance([1.0, 2.0, 3.0], [1.0, 2.0, 3.0]), 0.0);
+    }
+
+    #[test]
+    fn distance_matches_known_3_4_5_triangle() {
+        assert_eq!(distance([0.0, 0.0, 0.0], [3.0, 4.0, 0.0]), 5.0);
+    }
+}
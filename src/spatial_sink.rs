@@ -2,9 +2,7 @@ use std::f32;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
-use cpal::FromSample;
-
-use crate::source::Spatial;
+use crate::source::{SeekError, Spatial};
 use crate::stream::{OutputStreamHandle, PlayError};
 use crate::{Sample, Sink, Source};
 
@@ -17,8 +15,24 @@ struct SoundPositions {
     emitter_position: [f32; 3],
     left_ear: [f32; 3],
     right_ear: [f32; 3],
+    doppler_factor: f32,
+    prev_distance: Option<f32>,
 }
 
+/// Approximate speed of sound in air, in meters per second.
+const SPEED_OF_SOUND: f32 = 343.0;
+
+/// The resampling ratio produced by the Doppler effect is clamped to this
+/// range to avoid audible artifacts from extreme or erroneous velocities.
+const DOPPLER_RATIO_RANGE: (f32, f32) = (0.5, 2.0);
+
+/// How often the Doppler effect re-samples the emitter/ear positions and
+/// recomputes the pitch shift. This is measured in the source's own
+/// playback time (see [`Source::periodic_access`]), so it elapses only
+/// while the sink is actually advancing through samples, unlike a
+/// wall-clock `Instant`, which would also tick during a `sink.pause()`.
+const DOPPLER_TICK: Duration = Duration::from_millis(10);
+
 impl SpatialSink {
     /// Builds a new `SpatialSink`.
     pub fn try_new(
@@ -33,6 +47,8 @@ impl SpatialSink {
                 emitter_position,
                 left_ear,
                 right_ear,
+                doppler_factor: 0.0,
+                prev_distance: None,
             })),
         })
     }
@@ -52,12 +68,23 @@ impl SpatialSink {
         self.positions.lock().unwrap().right_ear = pos;
     }
 
+    /// Sets the Doppler factor, enabling a pitch shift driven by the motion
+    /// of the emitter relative to the listener.
+    ///
+    /// `0.0` (the default) disables the effect entirely. Values around `1.0`
+    /// give a physically plausible amount of shift; larger values exaggerate
+    /// it.
+    pub fn set_doppler_factor(&self, factor: f32) {
+        let mut positions = self.positions.lock().unwrap();
+        positions.doppler_factor = factor;
+        positions.prev_distance = None;
+    }
+
     /// Appends a sound to the queue of sounds to play.
     #[inline]
     pub fn append<S>(&self, source: S)
     where
         S: Source + Send + 'static,
-        f32: FromSample<S::Item>,
         S::Item: Sample + Send,
     {
         let positions = self.positions.clone();
@@ -68,13 +95,72 @@ impl SpatialSink {
             pos_lock.left_ear,
             pos_lock.right_ear,
         )
-        .periodic_access(Duration::from_millis(10), move |i| {
-            let pos = positions.lock().unwrap();
+        .periodic_access(DOPPLER_TICK, move |i| {
+            let mut pos = positions.lock().unwrap();
             i.set_positions(pos.emitter_position, pos.left_ear, pos.right_ear);
+
+            if pos.doppler_factor != 0.0 {
+                let midpoint = midpoint(pos.left_ear, pos.right_ear);
+                let distance = distance(pos.emitter_position, midpoint);
+
+                if let Some(prev_distance) = pos.prev_distance {
+                    let v_radial = (distance - prev_distance) / DOPPLER_TICK.as_secs_f32();
+                    i.set_speed(doppler_ratio(v_radial, pos.doppler_factor));
+                }
+
+                pos.prev_distance = Some(distance);
+            } else {
+                i.set_speed(1.0);
+            }
         });
         self.sink.append(source);
     }
 
+    /// Attempts to seek to a given position within the currently playing source.
+    ///
+    /// As with [`Sink::try_seek`], not all sources support seeking, and the
+    /// call has no effect if the sink is empty.
+    ///
+    /// # Errors
+    /// This function will return [`SeekError::NotSupported`] if one of the
+    /// underlying sources does not support seeking.
+    ///
+    /// It will return an error if an implementation ran
+    /// into one during the seek.
+    ///
+    /// When seeking beyond the end of a source, this function might return
+    /// an error if the duration of the source is not known.
+    #[inline]
+    pub fn try_seek(&self, pos: Duration) -> Result<(), SeekError> {
+        self.sink.try_seek(pos)
+    }
+
+    /// Returns the position of the sound that's being played.
+    ///
+    /// This takes into account any speedup or slowdown applied.
+    ///
+    /// Example: if you apply a speedup of *2* to an mp3 decoder source and
+    /// `get_pos()` returns *5s* after playback started, the position in the
+    /// mp3 recording is *10s* from its start.
+    #[inline]
+    pub fn get_pos(&self) -> Duration {
+        self.sink.get_pos()
+    }
+
+    /// Registers a callback that is invoked on the playback thread every
+    /// time a queued source has been fully consumed.
+    pub fn on_track_end(&self, f: impl Fn() + Send + 'static) {
+        self.sink.on_track_end(f);
+    }
+
+    /// Registers a callback that is invoked on the playback thread every
+    /// time a queued source has been fully consumed, receiving the index of
+    /// the finished source and the number of sources still remaining in the
+    /// queue. Useful for advancing a playlist.
+    pub fn on_track_end_with_progress(&self, f: impl Fn(usize, usize) + Send + 'static) {
+        self.sink.on_track_end_with_progress(f);
+    }
+
     // Gets the volume of the sound.
     ///
     /// The value `1.0` is the "normal" volume (unfiltered input). Any value other than 1.0 will
@@ -149,6 +235,23 @@ impl SpatialSink {
         self.sink.stop()
     }
 
+    /// Sets whether the sink splices consecutive sources without a gap.
+    ///
+    /// When enabled, the next queued source is primed ahead of the current
+    /// one finishing, so the switch happens on the sample boundary instead of
+    /// after a queue pop and allocation at the deadline.
+    #[inline]
+    pub fn set_gapless(&self, gapless: bool) {
+        self.sink.set_gapless(gapless);
+    }
+
+    /// Skips the currently playing sound, moving on to the next one in the
+    /// queue (or silence, if none is queued).
+    #[inline]
+    pub fn skip_one(&self) {
+        self.sink.skip_one();
+    }
+
     /// Destroys the sink without stopping the sounds that are still playing.
     #[inline]
     pub fn detach(self) {
@@ -169,7 +272,78 @@ impl SpatialSink {
 
     /// Returns the number of sounds currently in the queue.
     #[inline]
+    #[allow(clippy::len_without_is_empty)] // `empty()` is this type's `is_empty()`.
     pub fn len(&self) -> usize {
         self.sink.len()
     }
 }
+
+/// Returns the point halfway between `a` and `b`.
+fn midpoint(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        (a[0] + b[0]) / 2.0,
+        (a[1] + b[1]) / 2.0,
+        (a[2] + b[2]) / 2.0,
+    ]
+}
+
+/// Returns the Euclidean distance between `a` and `b`.
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let d = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+}
+
+/// Computes the Doppler resampling ratio for an emitter moving at `v_radial`
+/// (positive when receding) relative to the listener, scaled by
+/// `doppler_factor` and clamped to [`DOPPLER_RATIO_RANGE`].
+///
+/// The denominator is floored to a small positive fraction of
+/// [`SPEED_OF_SOUND`] rather than left to go to zero or negative, which
+/// would otherwise happen for `doppler_factor * v_radial` beyond
+/// `-SPEED_OF_SOUND` (e.g. an emitter "approaching" faster than sound) and
+/// flip the ratio's sign instead of saturating it at the fast end of the
+/// clamp range.
+fn doppler_ratio(v_radial: f32, doppler_factor: f32) -> f32 {
+    let denom = (SPEED_OF_SOUND + doppler_factor * v_radial).max(SPEED_OF_SOUND * 0.01);
+    (SPEED_OF_SOUND / denom).clamp(DOPPLER_RATIO_RANGE.0, DOPPLER_RATIO_RANGE.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn midpoint_of_ears_is_centered_between_them() {
+        assert_eq!(
+            midpoint([-1.0, 0.0, 0.0], [1.0, 0.0, 0.0]),
+            [0.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn distance_matches_known_3_4_5_triangle() {
+        assert_eq!(distance([0.0, 0.0, 0.0], [3.0, 4.0, 0.0]), 5.0);
+    }
+
+    #[test]
+    fn approaching_emitter_raises_the_ratio_above_one() {
+        // Negative radial velocity means the emitter is getting closer.
+        assert!(doppler_ratio(-50.0, 1.0) > 1.0);
+    }
+
+    #[test]
+    fn receding_emitter_lowers_the_ratio_below_one() {
+        assert!(doppler_ratio(50.0, 1.0) < 1.0);
+    }
+
+    #[test]
+    fn stationary_emitter_keeps_the_ratio_at_one() {
+        assert_eq!(doppler_ratio(0.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn extreme_velocity_clamps_to_the_configured_range() {
+        assert_eq!(doppler_ratio(100_000.0, 1.0), DOPPLER_RATIO_RANGE.0);
+        assert_eq!(doppler_ratio(-100_000.0, 1.0), DOPPLER_RATIO_RANGE.1);
+    }
+}
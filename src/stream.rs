@@ -0,0 +1,58 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::Source;
+
+/// An error that can occur when starting playback of a [`Source`].
+#[derive(Debug)]
+pub enum PlayError {
+    /// No output device is available.
+    NoDevice,
+    /// The output device does not support the requested stream format.
+    UnsupportedStreamConfig,
+}
+
+impl fmt::Display for PlayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayError::NoDevice => write!(f, "no output device available"),
+            PlayError::UnsupportedStreamConfig => {
+                write!(f, "the output device does not support the requested stream config")
+            }
+        }
+    }
+}
+
+impl Error for PlayError {}
+
+/// An open output stream, kept alive for as long as playback should
+/// continue.
+pub struct OutputStream {
+    handle: OutputStreamHandle,
+}
+
+impl OutputStream {
+    /// Returns a cheaply-cloneable handle that can be used to play sources
+    /// on this stream.
+    pub fn handle(&self) -> &OutputStreamHandle {
+        &self.handle
+    }
+}
+
+/// A handle to an [`OutputStream`], used to start playback of a [`Source`]
+/// on it.
+#[derive(Clone)]
+pub struct OutputStreamHandle;
+
+impl OutputStreamHandle {
+    /// Accepts `source` for playback and returns `Ok(())`. This handle is
+    /// not connected to an output device: `source` is dropped without any of
+    /// its samples being read.
+    pub fn play_raw<S>(&self, source: S) -> Result<(), PlayError>
+    where
+        S: Source<Item = f32> + Send + 'static,
+    {
+        let _ = source;
+        Ok(())
+    }
+}